@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// A minimal time-to-live cache: entries expire `ttl` after insertion. A read
+/// treats an expired entry as absent but leaves it in place; expired entries
+/// are only dropped in bulk via [`TtlCache::evict_expired`].
+pub struct TtlCache<K, V> {
+    ttl: Duration,
+    entries: HashMap<K, Entry<V>>,
+}
+
+struct Entry<V> {
+    value: V,
+    expires_at: Instant,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> TtlCache<K, V> {
+    pub fn new(ttl: Duration) -> Self {
+        TtlCache {
+            ttl,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Return a clone of the value for `key` when present and still fresh.
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.entries.get(key).and_then(|entry| {
+            if entry.expires_at > Instant::now() {
+                Some(entry.value.clone())
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        self.entries.insert(
+            key,
+            Entry {
+                value,
+                expires_at: Instant::now() + self.ttl,
+            },
+        );
+    }
+
+    pub fn remove(&mut self, key: &K) {
+        self.entries.remove(key);
+    }
+
+    /// Drop every entry whose TTL has elapsed.
+    pub fn evict_expired(&mut self) {
+        let now = Instant::now();
+        self.entries.retain(|_, entry| entry.expires_at > now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_then_get_returns_the_value() {
+        let mut cache = TtlCache::new(Duration::from_secs(60));
+        cache.insert("a", 1);
+        assert_eq!(cache.get(&"a"), Some(1));
+    }
+
+    #[test]
+    fn get_returns_none_for_a_missing_key() {
+        let cache: TtlCache<&str, i32> = TtlCache::new(Duration::from_secs(60));
+        assert_eq!(cache.get(&"missing"), None);
+    }
+
+    #[test]
+    fn get_returns_none_once_the_ttl_has_elapsed() {
+        let mut cache = TtlCache::new(Duration::from_millis(1));
+        cache.insert("a", 1);
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(cache.get(&"a"), None);
+    }
+
+    #[test]
+    fn remove_drops_the_entry() {
+        let mut cache = TtlCache::new(Duration::from_secs(60));
+        cache.insert("a", 1);
+        cache.remove(&"a");
+        assert_eq!(cache.get(&"a"), None);
+    }
+
+    #[test]
+    fn evict_expired_only_drops_stale_entries() {
+        let mut cache = TtlCache::new(Duration::from_millis(1));
+        cache.insert("stale", 1);
+        std::thread::sleep(Duration::from_millis(5));
+        cache.insert("fresh", 2);
+        cache.evict_expired();
+        assert_eq!(cache.get(&"fresh"), Some(2));
+        assert_eq!(cache.get(&"stale"), None);
+    }
+}
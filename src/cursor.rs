@@ -0,0 +1,40 @@
+use tide::Error;
+use uuid::Uuid;
+
+/// Encode a row id into an opaque forward cursor.
+pub fn encode(id: Uuid) -> String {
+    base64::encode(id.to_string())
+}
+
+/// Decode an opaque cursor back into a `Uuid`, rejecting malformed input with a
+/// `400 Bad Request`.
+pub fn decode(cursor: &str) -> tide::Result<Uuid> {
+    let bytes = base64::decode(cursor)
+        .map_err(|e| Error::from_str(400, format!("invalid cursor: {}", e)))?;
+    let text = String::from_utf8(bytes)
+        .map_err(|e| Error::from_str(400, format!("invalid cursor: {}", e)))?;
+    Uuid::parse_str(&text).map_err(|e| Error::from_str(400, format!("invalid cursor: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_a_uuid() {
+        let id = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        assert_eq!(decode(&encode(id)).unwrap(), id);
+    }
+
+    #[test]
+    fn rejects_malformed_cursor() {
+        let err = decode("not valid base64!").unwrap_err();
+        assert_eq!(err.status(), tide::StatusCode::BadRequest);
+    }
+
+    #[test]
+    fn rejects_valid_base64_that_is_not_a_uuid() {
+        let err = decode(&base64::encode("hello")).unwrap_err();
+        assert_eq!(err.status(), tide::StatusCode::BadRequest);
+    }
+}
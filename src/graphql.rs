@@ -0,0 +1,106 @@
+use async_graphql::{Context, EmptySubscription, Object, Schema};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::handlers::dino::{self, DinoQuery};
+use crate::{Dino, EntityCtx};
+
+pub type DinoSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+/// The conventional GraphQL-over-HTTP request envelope sent by the GraphiQL
+/// playground and standard GraphQL clients: `{"query": ..., "variables":
+/// {...}, "operationName": ...}`. `/graphql` deserializes the POST body into
+/// this before executing it, rather than treating the raw body as the query.
+#[derive(Debug, Deserialize)]
+pub struct GraphQLRequest {
+    query: String,
+    #[serde(default)]
+    variables: serde_json::Value,
+    #[serde(default, rename = "operationName")]
+    operation_name: Option<String>,
+}
+
+impl From<GraphQLRequest> for async_graphql::Request {
+    fn from(body: GraphQLRequest) -> Self {
+        let mut request = async_graphql::Request::new(body.query)
+            .variables(async_graphql::Variables::from_json(body.variables));
+        if let Some(name) = body.operation_name {
+            request = request.operation_name(name);
+        }
+        request
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn dinos(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Dino>> {
+        let entity_ctx = ctx.data::<EntityCtx>()?.clone();
+        let (dinos, _next_cursor) = dino::list(DinoQuery::default(), &entity_ctx).await?;
+        Ok(dinos)
+    }
+
+    async fn dino(&self, ctx: &Context<'_>, id: Uuid) -> async_graphql::Result<Option<Dino>> {
+        let entity_ctx = ctx.data::<EntityCtx>()?.clone();
+        let dino = dino::get(id, &entity_ctx).await?;
+        Ok(dino)
+    }
+}
+
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    async fn create_dino(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+        weight: i32,
+        diet: String,
+    ) -> async_graphql::Result<Dino> {
+        let entity_ctx = ctx.data::<EntityCtx>()?.clone();
+        let dino = Dino {
+            id: Some(Uuid::new_v4()),
+            name,
+            weight,
+            diet,
+        };
+        let dino = dino::create(dino, &entity_ctx).await?;
+        Ok(dino)
+    }
+
+    async fn update_dino(
+        &self,
+        ctx: &Context<'_>,
+        id: Uuid,
+        name: String,
+        weight: i32,
+        diet: String,
+    ) -> async_graphql::Result<Option<Dino>> {
+        let entity_ctx = ctx.data::<EntityCtx>()?.clone();
+        let dino = Dino {
+            id: Some(id),
+            name,
+            weight,
+            diet,
+        };
+        let dino = dino::update(id, dino, &entity_ctx).await?;
+        Ok(dino)
+    }
+
+    async fn delete_dino(&self, ctx: &Context<'_>, id: Uuid) -> async_graphql::Result<bool> {
+        let entity_ctx = ctx.data::<EntityCtx>()?.clone();
+        let deleted = dino::delete(id, &entity_ctx).await?;
+        Ok(deleted.is_some())
+    }
+}
+
+/// Build the GraphQL schema, carrying the same `EntityCtx` the REST routes
+/// build from `Request<State>`, so resolvers run through the identical
+/// query-building, caching, and job-enqueue path as `RestEntity`.
+pub fn build_schema(entity_ctx: EntityCtx) -> DinoSchema {
+    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .data(entity_ctx)
+        .finish()
+}
@@ -1,74 +1,45 @@
 use super::*;
-use crate::Dino;
-use sqlx::PgPool;
-use tide::Error;
-
-pub async fn create(dino: Dino, db_pool: PgPool) -> tide::Result<Dino> {
-    let row = sqlx::query_as::<_, Dino>(
-        "INSERT INTO dinos (id, name, weight, diet) VALUES
-                 ($1, $2, $3, $4) returning id, name, weight, diet",
-    )
-    .bind(dino.id)
-    .bind(dino.name)
-    .bind(dino.weight)
-    .bind(dino.diet)
-    .fetch_one(&db_pool)
-    .await
-    .map_err(|e| Error::new(409, e))?;
-
-    Ok(row)
+use crate::{create_entity, delete_entity, get_entity, list_entities, update_entity};
+use crate::{Dino, DinoFilter, EntityCtx};
+use serde::Deserialize;
+
+/// Filters and pagination accepted by [`list`].
+#[derive(Debug, Default, Deserialize)]
+pub struct DinoQuery {
+    pub limit: Option<i64>,
+    pub after: Option<String>,
+    pub diet: Option<String>,
+    pub min_weight: Option<i32>,
+    pub max_weight: Option<i32>,
 }
-pub async fn list(db_pool: PgPool) -> tide::Result<Vec<Dino>> {
-    let rows = sqlx::query_as::<_, Dino>("SELECT id, name, weight, diet from dinos")
-        .fetch_all(&db_pool)
-        .await
-        .map_err(|e| Error::new(409, e))?;
 
-    Ok(rows)
+impl From<&DinoQuery> for DinoFilter {
+    fn from(query: &DinoQuery) -> Self {
+        DinoFilter {
+            diet: query.diet.clone(),
+            min_weight: query.min_weight,
+            max_weight: query.max_weight,
+        }
+    }
 }
 
-pub async fn get(id: Uuid, db_pool: PgPool) -> tide::Result<Option<Dino>> {
-    let row = sqlx::query_as::<_, Dino>("SELECT id, name, weight, diet from dinos WHERE id = $1")
-        .bind(id)
-        .fetch_optional(&db_pool)
-        .await
-        .map_err(|e| Error::new(409, e))?;
-
-    Ok(row)
+pub async fn create(dino: Dino, ctx: &EntityCtx) -> tide::Result<Dino> {
+    create_entity(ctx, dino).await
 }
-pub async fn delete(id: Uuid, db_pool: PgPool) -> tide::Result<Option<()>> {
-    let row = sqlx::query(
-        "DELETE FROM dinos
-                WHERE id = $1
-                returning id",
-    )
-    .bind(id)
-    .fetch_optional(&db_pool)
-    .await
-    .map_err(|e| Error::new(409, e))?;
 
-    let r = match row {
-        None => None,
-        Some(_) => Some(()),
-    };
+pub async fn list(query: DinoQuery, ctx: &EntityCtx) -> tide::Result<(Vec<Dino>, Option<String>)> {
+    let filter = DinoFilter::from(&query);
+    list_entities::<Dino>(ctx, query.limit, query.after, &filter).await
+}
 
-    Ok(r)
+pub async fn get(id: Uuid, ctx: &EntityCtx) -> tide::Result<Option<Dino>> {
+    get_entity::<Dino>(ctx, id).await
 }
 
-pub async fn update(id: Uuid, dino: Dino, db_pool: PgPool) -> tide::Result<Option<Dino>> {
-    let row = sqlx::query_as::<_, Dino>(
-        "UPDATE dinos SET name = $2, weight = $3, diet = $4
-                WHERE id = $1
-                returning id, name, weight, diet
-                ",
-    )
-    .bind(id)
-    .bind(dino.name)
-    .bind(dino.weight)
-    .bind(dino.diet)
-    .fetch_optional(&db_pool)
-    .await
-    .map_err(|e| Error::new(409, e))?;
+pub async fn delete(id: Uuid, ctx: &EntityCtx) -> tide::Result<Option<()>> {
+    delete_entity::<Dino>(ctx, id).await
+}
 
-    Ok(row)
+pub async fn update(id: Uuid, dino: Dino, ctx: &EntityCtx) -> tide::Result<Option<Dino>> {
+    update_entity(ctx, id, dino).await
 }
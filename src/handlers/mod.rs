@@ -0,0 +1,3 @@
+pub mod dino;
+
+pub use uuid::Uuid;
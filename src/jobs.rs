@@ -0,0 +1,139 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgPool;
+use sqlx::types::Json;
+use uuid::Uuid;
+
+/// How long a claimed job may go without a heartbeat before the reaper assumes
+/// the worker crashed and returns it to the `new` state.
+const HEARTBEAT_TIMEOUT_SECS: i64 = 60;
+/// How long the worker sleeps when the queue is empty before polling again.
+const IDLE_POLL: Duration = Duration::from_secs(1);
+
+/// The queue name handlers push dino-related jobs onto.
+pub const DINO_QUEUE: &str = "dinos";
+
+/// The unit of deferred work, stored as tagged JSON in `job_queue.job`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Job {
+    /// Post-create enrichment for a freshly inserted dino.
+    EnrichDino { id: Uuid },
+    /// Periodic cleanup of orphaned rows.
+    Cleanup,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct QueuedJob {
+    id: Uuid,
+    job: Json<Job>,
+}
+
+/// Enqueue a job onto the named queue for a worker to pick up later.
+pub async fn push(db_pool: &PgPool, queue: &str, job: &Job) -> tide::Result<()> {
+    sqlx::query("INSERT INTO job_queue (queue, job) VALUES ($1, $2)")
+        .bind(queue)
+        .bind(Json(job))
+        .execute(db_pool)
+        .await?;
+    Ok(())
+}
+
+/// Atomically claim the oldest `new` job by insertion time (`id` is a random
+/// UUID, not insertion-ordered, so it can't stand in for FIFO order), marking
+/// it `running` and stamping its heartbeat. `SKIP LOCKED` lets many workers
+/// drain the queue concurrently.
+async fn claim(db_pool: &PgPool) -> tide::Result<Option<QueuedJob>> {
+    let job = sqlx::query_as::<_, QueuedJob>(
+        "UPDATE job_queue SET status = 'running', heartbeat = now()
+            WHERE id = (
+                SELECT id FROM job_queue
+                WHERE status = 'new'
+                ORDER BY created_at, id
+                FOR UPDATE SKIP LOCKED
+                LIMIT 1
+            )
+            RETURNING id, job",
+    )
+    .fetch_optional(db_pool)
+    .await?;
+    Ok(job)
+}
+
+/// Reset jobs whose heartbeat has gone stale back to `new` so a live worker can
+/// retry them — the source of the queue's at-least-once guarantee.
+async fn reap(db_pool: &PgPool) -> tide::Result<()> {
+    sqlx::query(&format!(
+        "UPDATE job_queue SET status = 'new', heartbeat = NULL
+            WHERE status = 'running'
+            AND heartbeat < now() - interval '{} seconds'",
+        HEARTBEAT_TIMEOUT_SECS
+    ))
+    .execute(db_pool)
+    .await?;
+    Ok(())
+}
+
+async fn handle(job: &Job, _db_pool: &PgPool) -> tide::Result<()> {
+    match job {
+        Job::EnrichDino { id } => {
+            tide::log::info!("enriching dino", { id: id.to_string() });
+        }
+        Job::Cleanup => {
+            tide::log::info!("running cleanup job");
+        }
+    }
+    Ok(())
+}
+
+/// Poll the queue forever, running each claimed job and deleting it on success.
+/// Spawned from `main()` with a clone of the shared pool.
+pub async fn run_worker(db_pool: PgPool) {
+    loop {
+        if let Err(e) = reap(&db_pool).await {
+            tide::log::error!("job reaper failed", { error: e.to_string() });
+        }
+
+        match claim(&db_pool).await {
+            Ok(Some(queued)) => {
+                if let Err(e) = handle(&queued.job, &db_pool).await {
+                    tide::log::error!("job handler failed", { error: e.to_string() });
+                    continue;
+                }
+                if let Err(e) = sqlx::query("DELETE FROM job_queue WHERE id = $1")
+                    .bind(queued.id)
+                    .execute(&db_pool)
+                    .await
+                {
+                    tide::log::error!("failed to delete completed job", { error: e.to_string() });
+                }
+            }
+            Ok(None) => async_std::task::sleep(IDLE_POLL).await,
+            Err(e) => {
+                tide::log::error!("failed to claim job", { error: e.to_string() });
+                async_std::task::sleep(IDLE_POLL).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enrich_dino_serializes_with_its_tag_and_id() {
+        let id = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        let json = serde_json::to_value(Job::EnrichDino { id }).unwrap();
+        assert_eq!(json["type"], "EnrichDino");
+        assert_eq!(json["id"], id.to_string());
+    }
+
+    #[test]
+    fn cleanup_round_trips_through_json() {
+        let json = serde_json::to_value(Job::Cleanup).unwrap();
+        let job: Job = serde_json::from_value(json).unwrap();
+        assert!(matches!(job, Job::Cleanup));
+    }
+}
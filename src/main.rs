@@ -1,22 +1,81 @@
 use dotenv;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use sqlx::{postgres::PgPool, Pool};
+use sqlx::postgres::{PgArguments, PgPoolOptions, PgRow};
+use sqlx::query::QueryAs;
+use sqlx::{postgres::PgPool, FromRow, Postgres};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use async_std::sync::RwLock;
+use cache::TtlCache;
 use tera::Tera;
 use tide::{Body, Request, Response, Server};
 use uuid::Uuid;
 
+mod cache;
 mod controller;
+mod cursor;
+mod graphql;
 mod handlers;
+mod jobs;
+mod migrations;
+
+/// Default and maximum page sizes for the cursor-paginated list endpoints.
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+/// Default time-to-live for cached `Dino` records, overridable via `CACHE_TTL_SECS`.
+const DEFAULT_CACHE_TTL_SECS: u64 = 30 * 60;
+/// Short-lived TTL for the cached unfiltered list.
+const LIST_CACHE_TTL: Duration = Duration::from_secs(10);
+
+/// Shared handle to the per-record read-through cache.
+type DinoCache = Arc<RwLock<TtlCache<Uuid, Dino>>>;
+/// Shared handle to the short-lived cached list of all dinos.
+type ListCache = Arc<RwLock<Option<CachedList>>>;
+
+/// A cached list snapshot together with its expiry instant.
+struct CachedList {
+    data: Vec<Dino>,
+    expires_at: Instant,
+}
+
+/// Bundles what the generic `*_entity` functions and `Entity`'s cache/job
+/// hooks need: a `PgPool` handle plus the optional per-record and list
+/// caches. Built from `Request<State>` for the REST routes and carried in the
+/// GraphQL schema's context for resolvers, so both entry points run the same
+/// query-building and caching code.
+#[derive(Clone)]
+struct EntityCtx {
+    db_pool: PgPool,
+    dino_cache: DinoCache,
+    list_cache: ListCache,
+}
+
+impl From<&State> for EntityCtx {
+    fn from(state: &State) -> Self {
+        EntityCtx {
+            db_pool: state.db_pool.clone(),
+            dino_cache: state.dino_cache.clone(),
+            list_cache: state.list_cache.clone(),
+        }
+    }
+}
 
 use controller::{dino, views};
+use graphql::{build_schema, DinoSchema, GraphQLRequest};
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 struct State {
     db_pool: PgPool,
     tera: Tera,
+    schema: DinoSchema,
+    dino_cache: DinoCache,
+    list_cache: ListCache,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, sqlx::FromRow)]
+#[derive(Debug, Deserialize, Serialize, Clone, sqlx::FromRow, async_graphql::SimpleObject)]
 struct Dino {
     #[serde(skip_serializing_if = "Option::is_none")]
     id: Option<Uuid>,
@@ -25,24 +84,292 @@ struct Dino {
     diet: String,
 }
 
-struct RestEntity {
+/// A resource that `RestEntity` can expose over CRUD routes.
+///
+/// Implementors describe their table and column layout so the generic handlers
+/// can build their SQL; `bind_columns` binds the row's values in the same order
+/// the `COLUMNS` slice lists them (the `id` column must come first). The
+/// `cache_*` hooks default to a no-op cache so entities without one behave
+/// exactly as before; `Dino` overrides them to share `EntityCtx`'s TTL caches.
+/// Both `RestEntity` (REST) and `handlers::dino` (GraphQL) run every read and
+/// write through the generic `*_entity` functions below, so this one set of
+/// hooks covers both surfaces.
+trait Entity:
+    DeserializeOwned + Serialize + Send + Sync + Unpin + 'static + for<'r> FromRow<'r, PgRow>
+{
+    /// Name of the backing table.
+    const TABLE_NAME: &'static str;
+    /// Columns in declaration order; the first entry must be the `id` column.
+    const COLUMNS: &'static [&'static str];
+
+    /// Entity-specific filters parsed from the list endpoint's query string,
+    /// alongside the shared [`Pagination`] parameters.
+    type ListFilter: DeserializeOwned + Default + Send;
+
+    /// Translate parsed filters into `WHERE` predicates for the generic list
+    /// handler to append. Defaults to no filters for entities that expose none.
+    fn filter_predicates(_filter: &Self::ListFilter) -> Vec<Filter> {
+        Vec::new()
+    }
+
+    /// Bind every column value, in `COLUMNS` order, onto a `query_as` builder.
+    fn bind_columns<'q>(
+        self,
+        query: QueryAs<'q, Postgres, Self, PgArguments>,
+    ) -> QueryAs<'q, Postgres, Self, PgArguments>;
+
+    /// Overwrite the row's id, used to honour the id carried in the request path.
+    fn set_id(&mut self, id: Uuid);
+
+    /// The row's id, used to mint the next-page cursor.
+    fn id(&self) -> Option<Uuid>;
+
+    /// Look up a cached copy of `id`. Entities that don't wire up a cache keep
+    /// the default, which always misses and falls through to Postgres.
+    async fn cache_get(_ctx: &EntityCtx, _id: Uuid) -> Option<Self> {
+        None
+    }
+
+    /// Cache a row just read from or written to Postgres, without disturbing
+    /// any cached list snapshot.
+    async fn cache_put(_ctx: &EntityCtx, _row: &Self) {}
+
+    /// Drop the cached copy of `id` together with the cached list, called
+    /// after a write that may have invalidated either.
+    async fn cache_invalidate(_ctx: &EntityCtx, _id: Uuid) {}
+
+    /// Look up a cached unfiltered first page holding at least `limit` rows.
+    /// Only called for cacheable list requests (no filter, no cursor); entities
+    /// that don't wire up a list cache keep the default, which always misses.
+    async fn cache_list_get(_ctx: &EntityCtx, _limit: i64) -> Option<Vec<Self>> {
+        None
+    }
+
+    /// Cache the rows of a cacheable unfiltered first page just read from
+    /// Postgres.
+    async fn cache_list_put(_ctx: &EntityCtx, _rows: &[Self]) {}
+
+    /// Run side effects after a row is inserted, e.g. enqueuing a background
+    /// job. Failures are logged, not propagated, so they never fail the create
+    /// itself. Defaults to nothing for entities that don't hook in.
+    async fn on_create(_ctx: &EntityCtx, _row: &Self) {}
+
+    fn columns_csv() -> String {
+        Self::COLUMNS.join(", ")
+    }
+
+    fn insert_sql() -> String {
+        let placeholders = (1..=Self::COLUMNS.len())
+            .map(|i| format!("${}", i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "INSERT INTO {} ({}) VALUES ({}) returning {}",
+            Self::TABLE_NAME,
+            Self::columns_csv(),
+            placeholders,
+            Self::columns_csv(),
+        )
+    }
+
+    fn update_sql() -> String {
+        let assignments = Self::COLUMNS
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, col)| format!("{} = ${}", col, i + 1))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "UPDATE {} SET {} WHERE id = $1 returning {}",
+            Self::TABLE_NAME,
+            assignments,
+            Self::columns_csv(),
+        )
+    }
+
+    fn list_sql() -> String {
+        format!("SELECT {} from {}", Self::columns_csv(), Self::TABLE_NAME)
+    }
+
+    fn get_sql() -> String {
+        format!(
+            "SELECT {} from {} WHERE id = $1",
+            Self::columns_csv(),
+            Self::TABLE_NAME,
+        )
+    }
+
+    fn delete_sql() -> String {
+        format!("DELETE FROM {} WHERE id = $1 returning id", Self::TABLE_NAME)
+    }
+}
+
+/// Filters the `/dinos` list endpoint accepts beyond the shared pagination
+/// parameters: an exact `diet` and an inclusive `weight` range.
+#[derive(Debug, Default, Deserialize)]
+struct DinoFilter {
+    diet: Option<String>,
+    min_weight: Option<i32>,
+    max_weight: Option<i32>,
+}
+
+impl Entity for Dino {
+    const TABLE_NAME: &'static str = "dinos";
+    const COLUMNS: &'static [&'static str] = &["id", "name", "weight", "diet"];
+
+    type ListFilter = DinoFilter;
+
+    fn filter_predicates(filter: &DinoFilter) -> Vec<Filter> {
+        let mut predicates = Vec::new();
+        if let Some(diet) = &filter.diet {
+            predicates.push(Filter {
+                column: "diet",
+                op: "=",
+                value: FilterValue::Text(diet.clone()),
+            });
+        }
+        if let Some(min_weight) = filter.min_weight {
+            predicates.push(Filter {
+                column: "weight",
+                op: ">=",
+                value: FilterValue::Int(min_weight),
+            });
+        }
+        if let Some(max_weight) = filter.max_weight {
+            predicates.push(Filter {
+                column: "weight",
+                op: "<=",
+                value: FilterValue::Int(max_weight),
+            });
+        }
+        predicates
+    }
+
+    fn bind_columns<'q>(
+        self,
+        query: QueryAs<'q, Postgres, Self, PgArguments>,
+    ) -> QueryAs<'q, Postgres, Self, PgArguments> {
+        query
+            .bind(self.id)
+            .bind(self.name)
+            .bind(self.weight)
+            .bind(self.diet)
+    }
+
+    fn set_id(&mut self, id: Uuid) {
+        self.id = Some(id);
+    }
+
+    fn id(&self) -> Option<Uuid> {
+        self.id
+    }
+
+    async fn cache_get(ctx: &EntityCtx, id: Uuid) -> Option<Self> {
+        ctx.dino_cache.read().await.get(&id)
+    }
+
+    async fn cache_put(ctx: &EntityCtx, row: &Self) {
+        if let Some(id) = row.id {
+            ctx.dino_cache.write().await.insert(id, row.clone());
+        }
+    }
+
+    async fn cache_invalidate(ctx: &EntityCtx, id: Uuid) {
+        ctx.dino_cache.write().await.remove(&id);
+        *ctx.list_cache.write().await = None;
+    }
+
+    async fn cache_list_get(ctx: &EntityCtx, limit: i64) -> Option<Vec<Self>> {
+        let cached = ctx.list_cache.read().await;
+        let cached = cached.as_ref()?;
+        // Serve the snapshot only when it holds at least `limit` rows,
+        // otherwise it may have been captured under a smaller limit and would
+        // under-report the page. Slice it down to the requested limit.
+        if cached.expires_at > Instant::now() && cached.data.len() as i64 >= limit {
+            Some(cached.data[..limit as usize].to_vec())
+        } else {
+            None
+        }
+    }
+
+    async fn cache_list_put(ctx: &EntityCtx, rows: &[Self]) {
+        *ctx.list_cache.write().await = Some(CachedList {
+            data: rows.to_vec(),
+            expires_at: Instant::now() + LIST_CACHE_TTL,
+        });
+    }
+
+    async fn on_create(ctx: &EntityCtx, row: &Self) {
+        if let Some(id) = row.id {
+            // Post-create enrichment runs off the request path; a failure to
+            // enqueue it shouldn't fail the create itself.
+            let job = jobs::Job::EnrichDino { id };
+            if let Err(e) = jobs::push(&ctx.db_pool, jobs::DINO_QUEUE, &job).await {
+                tide::log::error!("failed to enqueue dino enrichment job", { error: e.to_string() });
+            }
+        }
+    }
+}
+
+/// Query parameters shared by the cursor-paginated list endpoints.
+#[derive(Debug, Deserialize)]
+struct Pagination {
+    limit: Option<i64>,
+    after: Option<String>,
+}
+
+/// A single `WHERE` predicate contributed by an [`Entity::ListFilter`]: a
+/// column, the comparison operator, and the value to bind for its placeholder.
+struct Filter {
+    column: &'static str,
+    op: &'static str,
+    value: FilterValue,
+}
+
+/// A value bound into a filter predicate; one variant per column type the list
+/// endpoints can filter on.
+enum FilterValue {
+    Text(String),
+    Int(i32),
+}
+
+impl FilterValue {
+    fn bind<'q, E: Entity>(
+        self,
+        query: QueryAs<'q, Postgres, E, PgArguments>,
+    ) -> QueryAs<'q, Postgres, E, PgArguments> {
+        match self {
+            FilterValue::Text(v) => query.bind(v),
+            FilterValue::Int(v) => query.bind(v),
+        }
+    }
+}
+
+/// Envelope wrapping a page of rows plus the cursor for the next page.
+#[derive(Debug, Serialize)]
+struct Page<T> {
+    data: Vec<T>,
+    next_cursor: Option<String>,
+}
+
+struct RestEntity<E: Entity> {
     base_path: String,
+    _marker: PhantomData<E>,
 }
 
-impl RestEntity {
+impl<E: Entity> RestEntity<E> {
+    fn new(base_path: &str) -> Self {
+        RestEntity {
+            base_path: base_path.to_string(),
+            _marker: PhantomData,
+        }
+    }
+
     async fn create(mut req: Request<State>) -> tide::Result {
-        let dino: Dino = req.body_json().await?;
-        let db_pool = req.state().db_pool.clone();
-        let row = sqlx::query_as::<_, Dino>(
-            "INSERT INTO dinos (id, name, weight, diet) VALUES
-                 ($1, $2, $3, $4) returning id, name, weight, diet",
-        )
-        .bind(dino.id)
-        .bind(dino.name)
-        .bind(dino.weight)
-        .bind(dino.diet)
-        .fetch_one(&db_pool)
-        .await?;
+        let entity: E = req.body_json().await?;
+        let ctx = EntityCtx::from(req.state());
+        let row = create_entity(&ctx, entity).await?;
 
         let mut res = Response::new(201);
         res.set_body(Body::from_json(&row)?);
@@ -51,24 +378,27 @@ impl RestEntity {
     }
 
     async fn list(req: Request<State>) -> tide::Result {
-        let db_pool = req.state().db_pool.clone();
-        let rows = sqlx::query_as::<_, Dino>("SELECT id, name, weight, diet from dinos")
-            .fetch_all(&db_pool)
-            .await?;
+        let ctx = EntityCtx::from(req.state());
+        let params: Pagination = req.query()?;
+        let filter: E::ListFilter = req.query()?;
+
+        let (rows, next_cursor) =
+            list_entities::<E>(&ctx, params.limit, params.after, &filter).await?;
+
+        let page = Page {
+            data: rows,
+            next_cursor,
+        };
 
         let mut res = Response::new(200);
-        res.set_body(Body::from_json(&rows)?);
+        res.set_body(Body::from_json(&page)?);
         Ok(res)
     }
 
     async fn get(req: Request<State>) -> tide::Result {
-        let db_pool = req.state().db_pool.clone();
+        let ctx = EntityCtx::from(req.state());
         let id: Uuid = Uuid::parse_str(req.param("id")?).unwrap();
-        let row =
-            sqlx::query_as::<_, Dino>("SELECT id, name, weight, diet from dinos WHERE id = $1")
-                .bind(id)
-                .fetch_optional(&db_pool)
-                .await?;
+        let row = get_entity::<E>(&ctx, id).await?;
 
         let res = match row {
             None => Response::new(404),
@@ -83,21 +413,10 @@ impl RestEntity {
     }
 
     async fn update(mut req: Request<State>) -> tide::Result {
-        let dino: Dino = req.body_json().await?;
-        let db_pool = req.state().db_pool.clone();
+        let entity: E = req.body_json().await?;
+        let ctx = EntityCtx::from(req.state());
         let id: Uuid = Uuid::parse_str(req.param("id")?).unwrap();
-        let row = sqlx::query_as::<_, Dino>(
-            "UPDATE dinos SET name = $2, weight = $3, diet = $4
-                WHERE id = $1
-                returning id, name, weight, diet
-                ",
-        )
-        .bind(id)
-        .bind(dino.name)
-        .bind(dino.weight)
-        .bind(dino.diet)
-        .fetch_optional(&db_pool)
-        .await?;
+        let row = update_entity(&ctx, id, entity).await?;
 
         let res = match row {
             None => Response::new(404),
@@ -112,16 +431,9 @@ impl RestEntity {
     }
 
     async fn delete(req: Request<State>) -> tide::Result {
-        let db_pool = req.state().db_pool.clone();
+        let ctx = EntityCtx::from(req.state());
         let id: Uuid = Uuid::parse_str(req.param("id")?).unwrap();
-        let row = sqlx::query(
-            "DELETE FROM dinos
-                WHERE id = $1
-                returning id",
-        )
-        .bind(id)
-        .fetch_optional(&db_pool)
-        .await?;
+        let row = delete_entity::<E>(&ctx, id).await?;
 
         let res = match row {
             None => Response::new(404),
@@ -132,58 +444,317 @@ impl RestEntity {
     }
 }
 
+/// Insert a new row, then run the cache and post-create hooks. Shared by the
+/// REST `RestEntity::create` handler and `handlers::dino::create` (the
+/// GraphQL `createDino` mutation) so both surfaces write through one path.
+async fn create_entity<E: Entity>(ctx: &EntityCtx, entity: E) -> tide::Result<E> {
+    let row = entity
+        .bind_columns(sqlx::query_as::<_, E>(&E::insert_sql()))
+        .fetch_one(&ctx.db_pool)
+        .await
+        .map_err(|e| tide::Error::new(409, e))?;
+
+    if let Some(id) = row.id() {
+        E::cache_invalidate(ctx, id).await;
+    }
+    E::cache_put(ctx, &row).await;
+    E::on_create(ctx, &row).await;
+
+    Ok(row)
+}
+
+/// Fetch a row by id, consulting the cache first. Shared by the REST
+/// `RestEntity::get` handler and `handlers::dino::get` (the GraphQL `dino`
+/// query).
+async fn get_entity<E: Entity>(ctx: &EntityCtx, id: Uuid) -> tide::Result<Option<E>> {
+    if let Some(row) = E::cache_get(ctx, id).await {
+        return Ok(Some(row));
+    }
+
+    let row = sqlx::query_as::<_, E>(&E::get_sql())
+        .bind(id)
+        .fetch_optional(&ctx.db_pool)
+        .await
+        .map_err(|e| tide::Error::new(409, e))?;
+
+    if let Some(ref row) = row {
+        E::cache_put(ctx, row).await;
+    }
+
+    Ok(row)
+}
+
+/// Overwrite a row's columns (keeping its id), then invalidate the cache.
+/// Shared by the REST `RestEntity::update` handler and `handlers::dino::update`
+/// (the GraphQL `updateDino` mutation).
+async fn update_entity<E: Entity>(
+    ctx: &EntityCtx,
+    id: Uuid,
+    mut entity: E,
+) -> tide::Result<Option<E>> {
+    entity.set_id(id);
+    let row = entity
+        .bind_columns(sqlx::query_as::<_, E>(&E::update_sql()))
+        .fetch_optional(&ctx.db_pool)
+        .await
+        .map_err(|e| tide::Error::new(409, e))?;
+
+    E::cache_invalidate(ctx, id).await;
+
+    Ok(row)
+}
+
+/// Delete a row by id, then invalidate the cache. Shared by the REST
+/// `RestEntity::delete` handler and `handlers::dino::delete` (the GraphQL
+/// `deleteDino` mutation).
+async fn delete_entity<E: Entity>(ctx: &EntityCtx, id: Uuid) -> tide::Result<Option<()>> {
+    let row = sqlx::query(&E::delete_sql())
+        .bind(id)
+        .fetch_optional(&ctx.db_pool)
+        .await
+        .map_err(|e| tide::Error::new(409, e))?;
+
+    E::cache_invalidate(ctx, id).await;
+
+    Ok(row.map(|_| ()))
+}
+
+/// Run the cursor-paginated, filtered list query, consulting the cached
+/// unfiltered first page when the request is cacheable (no filter, no
+/// cursor). Shared by the REST `RestEntity::list` handler and
+/// `handlers::dino::list` (the GraphQL `dinos` query) so a fix to pagination,
+/// filtering, or caching lands in both places at once.
+async fn list_entities<E: Entity>(
+    ctx: &EntityCtx,
+    limit: Option<i64>,
+    after: Option<String>,
+    filter: &E::ListFilter,
+) -> tide::Result<(Vec<E>, Option<String>)> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let after = match after {
+        Some(ref c) => Some(cursor::decode(c)?),
+        None => None,
+    };
+
+    let predicates = E::filter_predicates(filter);
+    let cacheable = after.is_none() && predicates.is_empty();
+    if cacheable {
+        if let Some(rows) = E::cache_list_get(ctx, limit).await {
+            let next_cursor = rows.last().and_then(|row| row.id()).map(cursor::encode);
+            return Ok((rows, next_cursor));
+        }
+    }
+
+    // Assemble the WHERE clause incrementally so each filter and the cursor
+    // predicate get their own placeholder; the bindings below follow the
+    // same order.
+    let mut clauses: Vec<String> = Vec::new();
+    let mut idx = 1;
+    for predicate in &predicates {
+        clauses.push(format!("{} {} ${}", predicate.column, predicate.op, idx));
+        idx += 1;
+    }
+    if after.is_some() {
+        clauses.push(format!("id > ${}", idx));
+        idx += 1;
+    }
+
+    let mut sql = E::list_sql();
+    if !clauses.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&clauses.join(" AND "));
+    }
+    sql.push_str(&format!(" ORDER BY id LIMIT ${}", idx));
+
+    let mut query = sqlx::query_as::<_, E>(&sql);
+    for predicate in predicates {
+        query = predicate.value.bind(query);
+    }
+    if let Some(id) = after {
+        query = query.bind(id);
+    }
+    let rows = query
+        .bind(limit)
+        .fetch_all(&ctx.db_pool)
+        .await
+        .map_err(|e| tide::Error::new(409, e))?;
+
+    let next_cursor = if (rows.len() as i64) < limit {
+        None
+    } else {
+        rows.last().and_then(|row| row.id()).map(cursor::encode)
+    };
+
+    if cacheable {
+        E::cache_list_put(ctx, &rows).await;
+    }
+
+    Ok((rows, next_cursor))
+}
+
 #[async_std::main]
 async fn main() {
     dotenv::dotenv().ok();
 
     tide::log::start();
     let db_url = std::env::var("DATABASE_URL").unwrap();
-    let db_pool = make_db_pool(&db_url).await;
+    let db_pool = match make_db_pool(&db_url).await {
+        Ok(pool) => pool,
+        Err(e) => {
+            tide::log::error!("failed to connect to the database", { error: e.to_string() });
+            std::process::exit(1);
+        }
+    };
+    migrations::run_migrations(&db_pool)
+        .await
+        .expect("Failed to run migrations");
+
+    async_std::task::spawn(jobs::run_worker(db_pool.clone()));
+
     let app = server(db_pool).await;
 
     app.listen("127.0.0.1:8080").await.unwrap();
 }
 
-fn register_rest_entity(app: &mut Server<State>, entity: RestEntity) {
+fn register_rest_entity<E: Entity>(app: &mut Server<State>, entity: RestEntity<E>) {
     app.at(&entity.base_path)
-        .get(RestEntity::list)
-        .post(RestEntity::create);
+        .get(RestEntity::<E>::list)
+        .post(RestEntity::<E>::create);
 
     println!("{}/:id", &entity.base_path);
     app.at(&format!("{}/:id", &entity.base_path))
-        .get(RestEntity::get)
-        .put(RestEntity::update)
-        .delete(RestEntity::delete);
+        .get(RestEntity::<E>::get)
+        .put(RestEntity::<E>::update)
+        .delete(RestEntity::<E>::delete);
 }
 
-pub async fn make_db_pool(db_url: &str) -> PgPool {
-    Pool::connect(&db_url).await.unwrap()
+async fn handle_graphql(mut req: Request<State>) -> tide::Result {
+    let schema = req.state().schema.clone();
+    let body: GraphQLRequest = req.body_json().await?;
+    let gql_res = schema.execute(async_graphql::Request::from(body)).await;
+
+    let mut res = Response::new(200);
+    res.set_content_type(tide::http::mime::JSON);
+    res.set_body(Body::from_json(&gql_res)?);
+    Ok(res)
+}
+
+async fn handle_graphiql(_req: Request<State>) -> tide::Result {
+    let mut res = Response::new(200);
+    res.set_content_type(tide::http::mime::HTML);
+    res.set_body(async_graphql::http::playground_source(
+        async_graphql::http::GraphQLPlaygroundConfig::new("/graphql"),
+    ));
+    Ok(res)
+}
+
+/// Build the Postgres pool from environment-tuned options, probing it with a
+/// `SELECT 1` before returning and retrying with linear backoff so the server
+/// survives a database that is still coming up. Returns an error once the
+/// attempts are exhausted rather than panicking, letting `main()` exit cleanly.
+pub async fn make_db_pool(db_url: &str) -> Result<PgPool, sqlx::Error> {
+    fn env_parse<T: std::str::FromStr>(key: &str, default: T) -> T {
+        std::env::var(key)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default)
+    }
+
+    let max_connections = env_parse("DATABASE_MAX_CONNECTIONS", 5u32);
+    let min_connections = env_parse("DATABASE_MIN_CONNECTIONS", 0u32);
+    let acquire_timeout = env_parse("DATABASE_ACQUIRE_TIMEOUT", 30u64);
+
+    let options = PgPoolOptions::new()
+        .max_connections(max_connections)
+        .min_connections(min_connections)
+        .acquire_timeout(Duration::from_secs(acquire_timeout));
+
+    const MAX_ATTEMPTS: u32 = 5;
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        // Treat a failed health probe the same as a failed connect so a
+        // database that accepts connections before it is ready still gets
+        // retried rather than surfacing immediately.
+        let probe = async {
+            let pool = options.clone().connect(db_url).await?;
+            sqlx::query("SELECT 1").execute(&pool).await?;
+            Ok::<_, sqlx::Error>(pool)
+        }
+        .await;
+
+        match probe {
+            Ok(pool) => return Ok(pool),
+            Err(e) if attempt < MAX_ATTEMPTS => {
+                tide::log::warn!("database not ready, retrying", {
+                    attempt: attempt,
+                    error: e.to_string(),
+                });
+                async_std::task::sleep(Duration::from_secs(attempt as u64)).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
 }
 
 async fn server(db_pool: PgPool) -> Server<State> {
     let mut tera = Tera::new("templates/**/*").expect("Error parsing templates directory");
     tera.autoescape_on(vec!["html"]);
 
-    let state = State { db_pool, tera };
-
-    let dinos_endpoint = RestEntity {
-        base_path: String::from("/dinos"),
+    let ttl = std::env::var("CACHE_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or_else(|| Duration::from_secs(DEFAULT_CACHE_TTL_SECS));
+    let dino_cache: DinoCache = Arc::new(RwLock::new(TtlCache::new(ttl)));
+    let list_cache: ListCache = Arc::new(RwLock::new(None));
+
+    let entity_ctx = EntityCtx {
+        db_pool: db_pool.clone(),
+        dino_cache: dino_cache.clone(),
+        list_cache: list_cache.clone(),
     };
+    let schema = build_schema(entity_ctx);
+
+    // Periodically evict expired records so idle keys don't pin memory.
+    let evictor = dino_cache.clone();
+    async_std::task::spawn(async move {
+        loop {
+            async_std::task::sleep(ttl).await;
+            evictor.write().await.evict_expired();
+        }
+    });
+
+    let state = State {
+        db_pool,
+        tera,
+        schema,
+        dino_cache,
+        list_cache,
+    };
+
+    let dinos_endpoint = RestEntity::<Dino>::new("/dinos");
 
     let mut app = tide::with_state(state);
     app.at("/public")
         .serve_dir("./public/")
         .expect("Invalid static file directory");
 
+    app.at("/graphql").post(handle_graphql);
+    app.at("/graphql/playground").get(handle_graphiql);
+
     app.at("/").get(views::index);
     app.at("/dinos/new").get(views::new);
-    app.at("/dinos").get(dino::list).post(dino::create);
 
     app.at("/dinos/:id/edit")
         .get(dino::get)
         .put(dino::update)
         .delete(dino::delete);
 
+    // RestEntity::<Dino> (registered below) is the canonical REST mount for
+    // `/dinos` and `/dinos/:id`; it used to be registered alongside a second,
+    // identical `app.at("/dinos").get(dino::list).post(dino::create)` mount,
+    // which raced it for the same method and path.
     register_rest_entity(&mut app, dinos_endpoint);
 
     app
@@ -199,9 +770,31 @@ mod tests {
             std::env::var("DATABASE_URL_TEST").expect("missing env var DATABASE_URL_TEST");
     }
 
+    #[test]
+    fn page_serializes_next_cursor() {
+        let page = Page::<Dino> {
+            data: Vec::new(),
+            next_cursor: Some(String::from("abc")),
+        };
+        let json = serde_json::to_value(&page).unwrap();
+        assert_eq!(json["next_cursor"], "abc");
+        assert!(json["data"].is_array());
+    }
+
+    #[test]
+    fn page_serializes_null_cursor_on_last_page() {
+        let page = Page::<Dino> {
+            data: Vec::new(),
+            next_cursor: None,
+        };
+        let json = serde_json::to_value(&page).unwrap();
+        assert!(json["next_cursor"].is_null());
+    }
+
     async fn clear_dinos() -> Result<(), Box<dyn std::error::Error>> {
-        let db_pool = make_db_pool(&DB_URL).await;
+        let db_pool = make_db_pool(&DB_URL).await?;
 
+        migrations::run_migrations(&db_pool).await?;
         sqlx::query("DELETE FROM dinos").execute(&db_pool).await?;
         Ok(())
     }
@@ -213,7 +806,7 @@ mod tests {
             .await
             .expect("Failed to clear the dinos table");
 
-        let db_pool = make_db_pool(&DB_URL).await;
+        let db_pool = make_db_pool(&DB_URL).await?;
         let app = server(db_pool).await;
 
         let res = surf::Client::with_http_client(app)
@@ -239,7 +832,7 @@ mod tests {
             diet: String::from("carnivorous"),
         };
 
-        let db_pool = make_db_pool(&DB_URL).await;
+        let db_pool = make_db_pool(&DB_URL).await?;
         let app = server(db_pool).await;
 
         let mut res = surf::Client::with_http_client(app)
@@ -283,7 +876,7 @@ mod tests {
             diet: String::from("carnivorous"),
         };
 
-        let db_pool = make_db_pool(&DB_URL).await;
+        let db_pool = make_db_pool(&DB_URL).await?;
         sqlx::query_as::<_, Dino>(
             "INSERT INTO dinos (id, name, weight, diet) VALUES
             ($1, $2, $3, $4) returning id, name, weight, diet",
@@ -321,7 +914,7 @@ mod tests {
             diet: String::from("carnivorous"),
         };
 
-        let db_pool = make_db_pool(&DB_URL).await;
+        let db_pool = make_db_pool(&DB_URL).await?;
         sqlx::query_as::<_, Dino>(
             "INSERT INTO dinos (id, name, weight, diet) VALUES
             ($1, $2, $3, $4) returning id, name, weight, diet",
@@ -356,7 +949,7 @@ mod tests {
             weight: 50,
             diet: String::from("carnivorous"),
         };
-        let db_pool = make_db_pool(&DB_URL).await;
+        let db_pool = make_db_pool(&DB_URL).await?;
         sqlx::query_as::<_, Dino>(
             "INSERT INTO dinos (id, name, weight, diet) VALUES
             ($1, $2, $3, $4) returning id, name, weight, diet",
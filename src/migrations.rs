@@ -0,0 +1,61 @@
+use sqlx::postgres::PgPool;
+use sqlx::Executor;
+
+/// Directory holding the timestamped `.sql` migration files, resolved against
+/// the process's current working directory.
+const MIGRATIONS_DIR: &str = "migrations";
+
+/// Apply every migration in `migrations/` that hasn't been run yet.
+///
+/// A `_migrations` table tracks which files have already been applied by
+/// filename. Files are run in lexical (timestamp) order, each inside its own
+/// transaction so a failure leaves the bookkeeping table consistent.
+pub async fn run_migrations(db_pool: &PgPool) -> tide::Result<()> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS _migrations (
+            name VARCHAR PRIMARY KEY,
+            applied_at TIMESTAMP NOT NULL DEFAULT now()
+        )",
+    )
+    .execute(db_pool)
+    .await?;
+
+    let applied: Vec<String> = sqlx::query_scalar("SELECT name FROM _migrations")
+        .fetch_all(db_pool)
+        .await?;
+
+    let mut entries: Vec<_> = std::fs::read_dir(MIGRATIONS_DIR)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().map(|ext| ext == "sql").unwrap_or(false))
+        .collect();
+    entries.sort();
+
+    for path in entries {
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+            .to_string();
+
+        if applied.contains(&name) {
+            continue;
+        }
+
+        let sql = std::fs::read_to_string(&path)?;
+
+        let mut tx = db_pool.begin().await?;
+        // Run the whole file over the simple-query protocol; a file may hold
+        // several statements (e.g. a `DO $$…$$` block plus a `CREATE TABLE`),
+        // which the prepared protocol rejects as "multiple commands".
+        tx.execute(sql.as_str()).await?;
+        sqlx::query("INSERT INTO _migrations (name) VALUES ($1)")
+            .bind(&name)
+            .execute(&mut tx)
+            .await?;
+        tx.commit().await?;
+
+        tide::log::info!("applied migration", { name: name.as_str() });
+    }
+
+    Ok(())
+}